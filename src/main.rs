@@ -3,34 +3,85 @@ use {
     backoff::{ExponentialBackoff, future::retry},
     clap::{Parser, ValueEnum},
     futures::{future::TryFutureExt, sink::SinkExt, stream::StreamExt},
-    log::{error, info},
+    log::{error, info, warn},
     serde_json::{Value, json},
     solana_signature::Signature,
-    sqlx::postgres::PgPool,
+    sqlx::{Postgres, QueryBuilder, postgres::PgPool},
     std::{
-        collections::HashMap,
+        collections::{HashMap, HashSet, VecDeque},
         env,
+        hash::Hash,
         sync::Arc,
-        time::{SystemTime, UNIX_EPOCH},
+        time::{Duration, SystemTime, UNIX_EPOCH},
     },
-    tokio::sync::Mutex,
+    tokio::sync::{Mutex, mpsc},
     tonic::transport::channel::ClientTlsConfig,
     yellowstone_grpc_client::{GeyserGrpcClient, Interceptor},
     yellowstone_grpc_proto::{
         convert_from::create_pubkey_vec,
         prelude::{
-            CommitmentLevel, SubscribeRequest, SubscribeRequestFilterTransactions,
-            SubscribeRequestPing, SubscribeUpdateTransactionInfo, subscribe_update::UpdateOneof,
+            CommitmentLevel, SubscribeRequest, SubscribeRequestFilterAccounts,
+            SubscribeRequestFilterSlots, SubscribeRequestFilterTransactions, SubscribeRequestPing,
+            SubscribeUpdateAccountInfo, SubscribeUpdateSlot, SubscribeUpdateTransactionInfo,
+            subscribe_update::UpdateOneof,
         },
     },
 };
 
+/// Number of recently seen transaction signatures kept for deduplication. A
+/// Solana slot holds a few thousand transactions, so this covers roughly the
+/// last few dozen slots' worth of traffic before a signature is evicted.
+const SEEN_SIGNATURE_CAPACITY: usize = 100_000;
+
+/// Number of recently seen account writes kept for deduplication, keyed by
+/// `(pubkey, slot, write_version)`. Sized like [`SEEN_SIGNATURE_CAPACITY`] so it
+/// spans roughly the same recent window of traffic.
+const SEEN_ACCOUNT_CAPACITY: usize = 100_000;
+
+/// Number of recently seen slot transitions kept for deduplication, keyed by
+/// `(slot, commitment status)`. Slots move through far fewer updates than
+/// transactions, so a smaller window comfortably covers the reorder horizon.
+const SEEN_SLOT_CAPACITY: usize = 10_000;
+
+/// Channel depth between the per-source stream tasks and the single consumer.
+const EVENT_CHANNEL_CAPACITY: usize = 10_000;
+
+/// Flush the buffered records once this many have accumulated.
+const FLUSH_BATCH_SIZE: usize = 500;
+
+/// Flush the buffered records at least this often, even below the batch size,
+/// so low-traffic periods still land in the database promptly.
+const FLUSH_INTERVAL_MS: u64 = 500;
+
+/// Well-known pubkey of the compute-budget program whose instructions carry the
+/// priority-fee bids we care about.
+const COMPUTE_BUDGET_PROGRAM_ID: &str = "ComputeBudget111111111111111111111111111111";
+
+/// Instruction discriminators (first data byte) for the two compute-budget
+/// instructions we decode.
+const SET_COMPUTE_UNIT_LIMIT_DISCRIMINATOR: u8 = 2;
+const SET_COMPUTE_UNIT_PRICE_DISCRIMINATOR: u8 = 3;
+
+/// Per-instruction compute-unit allotment the runtime applies when a
+/// transaction carries no explicit `SetComputeUnitLimit`. The effective default
+/// limit is this value times the instruction count, capped at
+/// [`MAX_COMPUTE_UNIT_LIMIT`], so aggregate queries stay null-free and match what
+/// the network actually budgeted.
+const DEFAULT_INSTRUCTION_COMPUTE_UNIT_LIMIT: u32 = 200_000;
+
+/// Hard ceiling the runtime places on a transaction's compute-unit limit,
+/// whether requested explicitly or derived from the per-instruction default.
+const MAX_COMPUTE_UNIT_LIMIT: u32 = 1_400_000;
+
+/// Network default compute-unit price (no priority-fee bid) in micro-lamports.
+const DEFAULT_COMPUTE_UNIT_PRICE: u64 = 0;
+
 #[derive(Debug, Clone, Parser)]
 #[clap(author, version, about)]
 struct Args {
-    #[clap(short, long, default_value_t = String::from("http://127.0.0.1:10000"))]
-    /// Service endpoint
-    endpoint: String,
+    #[clap(short, long, default_values_t = [String::from("http://127.0.0.1:10000")])]
+    /// Service endpoint(s); repeat to consume several gRPC sources concurrently
+    endpoint: Vec<String>,
 
     #[clap(long)]
     x_token: Option<String>,
@@ -39,19 +90,45 @@ struct Args {
     #[clap(long)]
     accounts: Vec<String>,
 
+    /// Subscribe to account writes for these specific pubkeys
+    #[clap(long = "account")]
+    account_keys: Vec<String>,
+
+    /// Subscribe to account writes owned by these programs
+    #[clap(long = "owner")]
+    owners: Vec<String>,
+
     /// Commitment level: processed, confirmed or finalized
     #[clap(long)]
     commitment: Option<ArgsCommitment>,
+
+    /// Commitment level for the account subscription; defaults to --commitment
+    #[clap(long)]
+    accounts_commitment: Option<ArgsCommitment>,
 }
 
 impl Args {
     fn get_commitment(&self) -> Option<CommitmentLevel> {
-        Some(ArgsCommitment::default().into())
+        Some(self.commitment.unwrap_or_default().into())
+    }
+
+    /// Commitment for the account subscription, falling back to the transaction
+    /// commitment when `--accounts-commitment` is not given.
+    fn get_accounts_commitment(&self) -> Option<CommitmentLevel> {
+        match self.accounts_commitment {
+            Some(commitment) => Some(commitment.into()),
+            None => self.get_commitment(),
+        }
     }
 
-    async fn connect(&self) -> anyhow::Result<GeyserGrpcClient<impl Interceptor>> {
+    /// Whether the user asked for any account subscription at all.
+    fn has_account_subscription(&self) -> bool {
+        !self.account_keys.is_empty() || !self.owners.is_empty()
+    }
+
+    async fn connect(&self, endpoint: &str) -> anyhow::Result<GeyserGrpcClient<impl Interceptor>> {
         let tls_config = ClientTlsConfig::new().with_enabled_roots();
-        let builder = GeyserGrpcClient::build_from_shared(self.endpoint.clone())?
+        let builder = GeyserGrpcClient::build_from_shared(endpoint.to_string())?
             .x_token(self.x_token.clone())?
             .tls_config(tls_config)?;
         builder.connect().await.map_err(Into::into)
@@ -76,37 +153,143 @@ impl From<ArgsCommitment> for CommitmentLevel {
     }
 }
 
+/// Which stream a source task opens. Transactions and accounts are subscribed
+/// independently so each can run at its own commitment level.
+#[derive(Debug, Clone, Copy)]
+enum Subscription {
+    Transactions,
+    Accounts,
+    Slots,
+}
+
+/// An update pulled off one source stream, forwarded to the central consumer
+/// for deduplication and persistence.
+enum Event {
+    Transaction(TransactionEvent),
+    Account(AccountEvent),
+    Slot(SlotEvent),
+}
+
+/// A transaction update pulled off one source stream, forwarded to the central
+/// consumer for deduplication and persistence.
+struct TransactionEvent {
+    created_at: SystemTime,
+    filters: Vec<String>,
+    transaction: SubscribeUpdateTransactionInfo,
+}
+
+/// An account-write snapshot pulled off one source stream.
+struct AccountEvent {
+    account: SubscribeUpdateAccountInfo,
+    slot: u64,
+}
+
+/// A slot update and its commitment transition, used to audit the stream for
+/// gaps.
+struct SlotEvent {
+    slot: SubscribeUpdateSlot,
+}
+
+/// Bounded record of recently seen keys. Backed by a ring buffer so memory stays
+/// flat: once `capacity` keys are held the oldest is evicted as a new one is
+/// recorded. The same structure deduplicates transaction signatures, slot
+/// transitions and account writes as each redundant source re-delivers them.
+struct SeenSet<K: Eq + Hash + Clone> {
+    set: HashSet<K>,
+    ring: VecDeque<K>,
+    capacity: usize,
+}
+
+impl<K: Eq + Hash + Clone> SeenSet<K> {
+    fn new(capacity: usize) -> Self {
+        Self {
+            set: HashSet::with_capacity(capacity),
+            ring: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// Record `key`, returning `true` if it had not been seen before and
+    /// `false` if it is a duplicate that should be dropped.
+    fn insert(&mut self, key: K) -> bool {
+        if self.set.contains(&key) {
+            return false;
+        }
+        if self.ring.len() == self.capacity {
+            if let Some(evicted) = self.ring.pop_front() {
+                self.set.remove(&evicted);
+            }
+        }
+        self.ring.push_back(key.clone());
+        self.set.insert(key);
+        true
+    }
+}
+
+/// Build the subscribe request for a single subscription. Transactions and
+/// accounts are built as separate requests so each carries its own commitment
+/// level (the gRPC request has a single global commitment per stream), which is
+/// how processed accounts can be correlated against confirmed transactions.
 fn get_subscribe_request(
     args: &Args,
-    commitment: Option<CommitmentLevel>,
+    subscription: Subscription,
 ) -> anyhow::Result<Option<SubscribeRequest>> {
-    Ok({
-        let mut transactions = HashMap::new();
-        transactions.insert(
-            "client".to_string(),
-            SubscribeRequestFilterTransactions {
-                vote: Some(false),
-                failed: Some(false),
-                signature: None,
-                account_include: args.accounts.clone(),
-                account_exclude: vec![],
-                account_required: vec![],
-            },
-        );
-        Some(SubscribeRequest {
-            accounts: HashMap::new(),
-            slots: HashMap::new(),
-            transactions,
-            transactions_status: HashMap::new(),
-            blocks: HashMap::new(),
-            blocks_meta: HashMap::new(),
-            entry: HashMap::new(),
-            commitment: commitment.map(|x| x as i32),
-            accounts_data_slice: Vec::new(),
-            ping: None,
-            from_slot: None,
-        })
-    })
+    let mut transactions = HashMap::new();
+    let mut accounts = HashMap::new();
+    let mut slots = HashMap::new();
+    let commitment = match subscription {
+        Subscription::Transactions => {
+            transactions.insert(
+                "client".to_string(),
+                SubscribeRequestFilterTransactions {
+                    vote: Some(false),
+                    failed: Some(false),
+                    signature: None,
+                    account_include: args.accounts.clone(),
+                    account_exclude: vec![],
+                    account_required: vec![],
+                },
+            );
+            args.get_commitment()
+        }
+        Subscription::Accounts => {
+            accounts.insert(
+                "client".to_string(),
+                SubscribeRequestFilterAccounts {
+                    account: args.account_keys.clone(),
+                    owner: args.owners.clone(),
+                    filters: vec![],
+                    nonempty_txn_signature: None,
+                },
+            );
+            args.get_accounts_commitment()
+        }
+        Subscription::Slots => {
+            // Observe every commitment transition so gaps are visible regardless
+            // of the requested commitment level.
+            slots.insert(
+                "client".to_string(),
+                SubscribeRequestFilterSlots {
+                    filter_by_commitment: Some(false),
+                    interslot_updates: None,
+                },
+            );
+            args.get_commitment()
+        }
+    };
+    Ok(Some(SubscribeRequest {
+        accounts,
+        slots,
+        transactions,
+        transactions_status: HashMap::new(),
+        blocks: HashMap::new(),
+        blocks_meta: HashMap::new(),
+        entry: HashMap::new(),
+        commitment: commitment.map(|x| x as i32),
+        accounts_data_slice: Vec::new(),
+        ping: None,
+        from_slot: None,
+    }))
 }
 
 #[tokio::main]
@@ -120,53 +303,250 @@ async fn main() -> anyhow::Result<()> {
     env_logger::init();
 
     let args = Args::parse();
+    let db_url = env::var("POSTGRES_DB_URL").context("POSTGRES_DB_URL must be set")?;
+    // Retry the initial connection with the same backoff the sources use so a
+    // Postgres that is slow to come up doesn't abort the collector at startup.
+    let pool = retry(ExponentialBackoff::default(), || async {
+        PgPool::connect(&db_url)
+            .await
+            .map_err(backoff::Error::transient)
+    })
+    .await
+    .context("failed to connect to Postgres")?;
+
+    // Each endpoint runs its own reconnect/backoff loop and forwards updates
+    // onto a shared channel; a single consumer deduplicates by signature so
+    // whichever source delivers a transaction first wins. Each endpoint opens a
+    // transaction stream and a slot stream; when account subscriptions are
+    // requested it opens a third, independent stream so accounts and
+    // transactions can run at different commitments.
+    let (sender, receiver) = mpsc::channel(EVENT_CHANNEL_CAPACITY);
+    let mut handles = Vec::new();
+    for endpoint in args.endpoint.iter().cloned() {
+        let mut subscriptions = vec![Subscription::Transactions, Subscription::Slots];
+        if args.has_account_subscription() {
+            subscriptions.push(Subscription::Accounts);
+        }
+        for subscription in subscriptions {
+            let args = args.clone();
+            let sender = sender.clone();
+            let endpoint = endpoint.clone();
+            handles.push(tokio::spawn(run_source(
+                endpoint,
+                args,
+                subscription,
+                sender,
+            )));
+        }
+    }
+    drop(sender);
+
+    consume_events(pool, receiver).await?;
+
+    for handle in handles {
+        handle.await?;
+    }
+    Ok(())
+}
+
+/// Own reconnect loop for a single gRPC source. A dead endpoint retries here in
+/// isolation, so it never stalls the sibling sources feeding the same channel.
+async fn run_source(
+    endpoint: String,
+    args: Args,
+    subscription: Subscription,
+    sender: mpsc::Sender<Event>,
+) {
     let zero_attempts = Arc::new(Mutex::new(true));
 
     // The default exponential backoff strategy intervals:
     // [500ms, 750ms, 1.125s, 1.6875s, 2.53125s, 3.796875s, 5.6953125s,
     // 8.5s, 12.8s, 19.2s, 28.8s, 43.2s, 64.8s, 97s, ... ]
-    retry(ExponentialBackoff::default(), move || {
+    let result = retry(ExponentialBackoff::default(), move || {
+        let endpoint = endpoint.clone();
         let args = args.clone();
+        let sender = sender.clone();
         let zero_attempts = Arc::clone(&zero_attempts);
 
         async move {
-            let pool = PgPool::connect(&env::var("POSTGRES_DB_URL").unwrap())
-                .await
-                .unwrap();
-
             let mut zero_attempts = zero_attempts.lock().await;
             if *zero_attempts {
                 *zero_attempts = false;
             } else {
-                info!("Retry to connect to the server");
+                info!("Retry to connect to {endpoint}");
             }
             drop(zero_attempts);
 
-            let commitment = args.get_commitment();
-            let client = args.connect().await.map_err(backoff::Error::transient)?;
-            info!("Connected");
-            let request = get_subscribe_request(&args, commitment)
+            let client = args
+                .connect(&endpoint)
+                .await
+                .map_err(backoff::Error::transient)?;
+            info!("Connected to {endpoint}");
+            let request = get_subscribe_request(&args, subscription)
                 .map_err(backoff::Error::Permanent)?
                 .ok_or(backoff::Error::Permanent(anyhow::anyhow!(
                     "expect subscribe action"
                 )))?;
 
-            geyser_subscribe(pool, client, request)
+            geyser_subscribe(client, request, &sender)
                 .await
                 .map_err(backoff::Error::transient)?;
 
             Ok::<(), backoff::Error<anyhow::Error>>(())
         }
-        .inspect_err(|error| error!("failed to connect: {error}"))
+        .inspect_err(move |error| error!("source failed: {error}"))
     })
-    .await
-    .map_err(Into::into)
+    .await;
+
+    if let Err(error) = result {
+        error!("source exhausted retries: {error}");
+    }
+}
+
+/// Central consumer: deduplicates incoming updates (transactions by signature,
+/// account writes by pubkey, slot and write version, slot updates by slot and
+/// status) and persists the first occurrence of each as a transaction,
+/// account-snapshot or slot record.
+async fn consume_events(pool: PgPool, mut receiver: mpsc::Receiver<Event>) -> anyhow::Result<()> {
+    let mut seen = SeenSet::new(SEEN_SIGNATURE_CAPACITY);
+    let mut seen_accounts = SeenSet::new(SEEN_ACCOUNT_CAPACITY);
+    let mut seen_slots = SeenSet::new(SEEN_SLOT_CAPACITY);
+    // Records buffer here between the stream and the pool so the gRPC consumer
+    // never blocks on individual DB writes; they flush as one multi-row INSERT
+    // every FLUSH_BATCH_SIZE records or every FLUSH_INTERVAL_MS, whichever comes
+    // first.
+    let mut txns: Vec<Value> = Vec::with_capacity(FLUSH_BATCH_SIZE);
+    let mut accounts: Vec<Value> = Vec::with_capacity(FLUSH_BATCH_SIZE);
+    let mut slots: Vec<Value> = Vec::with_capacity(FLUSH_BATCH_SIZE);
+    // Highest finalized slot observed so far. Gap detection compares each newly
+    // finalized slot's `parent` (its predecessor in the rooted chain) against
+    // this: if the parent is a rooted slot we never observed, finalized slots
+    // went missing between them. A bare slot-number delta cannot tell a
+    // leader-skipped slot (never rooted, so never reported at any commitment)
+    // from data the collector dropped — both look like a jump — so this is a
+    // soft warning, not an error, and the parent link only narrows it to
+    // "rooted slots we didn't see".
+    let mut last_finalized_slot: Option<u64> = None;
+    let mut ticker = tokio::time::interval(Duration::from_millis(FLUSH_INTERVAL_MS));
+    loop {
+        tokio::select! {
+            message = receiver.recv() => match message {
+                Some(Event::Transaction(event)) => {
+                    let signature = match Signature::try_from(event.transaction.signature.as_slice()) {
+                        Ok(signature) => signature,
+                        Err(error) => {
+                            error!("skipping transaction with invalid signature: {error}");
+                            continue;
+                        }
+                    };
+                    // A malformed update must not take the collector down: this
+                    // consumer runs outside the per-source backoff loop, so a
+                    // propagated error would exit `main`. Log and skip instead.
+                    // Decode *before* recording the signature as seen: otherwise a
+                    // source delivering a malformed copy first would mark it seen
+                    // and the good copy from a redundant source would be dropped
+                    // as a duplicate, defeating the fastest-wins redundancy.
+                    let mut value = match create_pretty_transaction(event.transaction) {
+                        Ok(value) => value,
+                        Err(error) => {
+                            error!("skipping malformed transaction {signature}: {error}");
+                            continue;
+                        }
+                    };
+                    if !seen.insert(signature) {
+                        continue;
+                    }
+                    value["unix_epoch"] = event
+                        .created_at
+                        .duration_since(UNIX_EPOCH)
+                        .unwrap()
+                        .as_secs()
+                        .into();
+                    print_update("transaction", event.created_at, &event.filters, &value);
+                    txns.push(value);
+                    if txns.len() >= FLUSH_BATCH_SIZE {
+                        flush_records(&pool, &mut txns).await?;
+                    }
+                }
+                Some(Event::Account(event)) => {
+                    // As with transactions, a malformed account update must be
+                    // logged and skipped rather than propagated — this consumer
+                    // has no enclosing backoff loop, so `?` would kill the
+                    // collector.
+                    let value = match create_pretty_account(&event) {
+                        Ok(value) => value,
+                        Err(error) => {
+                            error!("skipping malformed account update: {error}");
+                            continue;
+                        }
+                    };
+                    // Each endpoint delivers the same write independently; drop
+                    // the duplicates so the table holds each write once.
+                    let key = (
+                        value["pubkey"].as_str().unwrap_or_default().to_string(),
+                        event.slot,
+                        event.account.write_version,
+                    );
+                    if !seen_accounts.insert(key) {
+                        continue;
+                    }
+                    accounts.push(value);
+                    if accounts.len() >= FLUSH_BATCH_SIZE {
+                        flush_accounts(&pool, &mut accounts).await?;
+                    }
+                }
+                Some(Event::Slot(event)) => {
+                    let slot = event.slot.slot;
+                    let status = event.slot.status;
+                    // Every source reports each transition; keep one row per
+                    // (slot, commitment) so the table isn't N× inflated.
+                    if !seen_slots.insert((slot, status)) {
+                        continue;
+                    }
+                    if status == CommitmentLevel::Finalized as i32 {
+                        if let (Some(previous), Some(parent)) =
+                            (last_finalized_slot, event.slot.parent)
+                        {
+                            if parent > previous {
+                                warn!(
+                                    "possible gap on finalized frontier: slot {slot} roots onto \
+                                     parent {parent}, but the last finalized slot observed was \
+                                     {previous} ({} rooted slot(s) unobserved; skipped-leader \
+                                     slots are indistinguishable from dropped data)",
+                                    parent - previous
+                                );
+                            }
+                        }
+                        if last_finalized_slot.is_none_or(|previous| slot > previous) {
+                            last_finalized_slot = Some(slot);
+                        }
+                    }
+                    slots.push(create_pretty_slot(&event));
+                    if slots.len() >= FLUSH_BATCH_SIZE {
+                        flush_slots(&pool, &mut slots).await?;
+                    }
+                }
+                None => break,
+            },
+            _ = ticker.tick() => {
+                flush_records(&pool, &mut txns).await?;
+                flush_accounts(&pool, &mut accounts).await?;
+                flush_slots(&pool, &mut slots).await?;
+            }
+        }
+    }
+    // Drain whatever is left once every source has closed.
+    flush_records(&pool, &mut txns).await?;
+    flush_accounts(&pool, &mut accounts).await?;
+    flush_slots(&pool, &mut slots).await?;
+    info!("all sources closed");
+    Ok(())
 }
 
 async fn geyser_subscribe(
-    pool: PgPool,
     mut client: GeyserGrpcClient<impl Interceptor>,
     request: SubscribeRequest,
+    sender: &mpsc::Sender<Event>,
 ) -> anyhow::Result<()> {
     let (mut subscribe_tx, mut stream) = client.subscribe_with_request(Some(request)).await?;
     info!("stream opened");
@@ -184,14 +564,33 @@ async fn geyser_subscribe(
                         let tx = msg
                             .transaction
                             .ok_or(anyhow::anyhow!("no transaction in the message"))?;
-                        let mut value = create_pretty_transaction(tx)?;
-                        value["unix_epoch"] = created_at
-                            .duration_since(UNIX_EPOCH)
-                            .unwrap()
-                            .as_secs()
-                            .into();
-                        add_record(&pool, &value).await.unwrap();
-                        print_update("transaction", created_at, &filters, value);
+                        let event = Event::Transaction(TransactionEvent {
+                            created_at,
+                            filters,
+                            transaction: tx,
+                        });
+                        if sender.send(event).await.is_err() {
+                            // Consumer is gone; nothing left to forward to.
+                            break;
+                        }
+                    }
+                    Some(UpdateOneof::Account(msg)) => {
+                        let account = msg
+                            .account
+                            .ok_or(anyhow::anyhow!("no account in the message"))?;
+                        let event = Event::Account(AccountEvent {
+                            account,
+                            slot: msg.slot,
+                        });
+                        if sender.send(event).await.is_err() {
+                            break;
+                        }
+                    }
+                    Some(UpdateOneof::Slot(msg)) => {
+                        let event = Event::Slot(SlotEvent { slot: msg });
+                        if sender.send(event).await.is_err() {
+                            break;
+                        }
                     }
                     Some(UpdateOneof::Ping(_)) => {
                         // This is necessary to keep load balancers that expect client pings alive. If your load balancer doesn't
@@ -226,24 +625,162 @@ async fn geyser_subscribe(
 }
 
 fn create_pretty_transaction(tx: SubscribeUpdateTransactionInfo) -> anyhow::Result<Value> {
-    let signer: String = create_pubkey_vec(
-        tx.transaction
-            .as_ref()
-            .map(|t| t.message.as_ref().map(|m| m.account_keys.clone()))
-            .flatten()
-            .unwrap(),
-    )
-    .unwrap()[0]
-        .to_string();
-    let fee = tx.clone().meta.unwrap().fee;
+    let transaction = tx.transaction.as_ref().context("no transaction")?;
+    let message = transaction.message.as_ref().context("no message")?;
+    let meta = tx.meta.as_ref().context("no meta")?;
+
+    let account_keys: Vec<String> = create_pubkey_vec(message.account_keys.clone())
+        .map_err(|error| anyhow::anyhow!("invalid account keys: {error:?}"))?
+        .iter()
+        .map(|key| key.to_string())
+        .collect();
+    let signer = account_keys
+        .first()
+        .context("transaction has no account keys")?
+        .clone();
+    let fee = meta.fee;
+
+    // Walk the compute-budget instructions to recover the priority-fee bid.
+    // Transactions that omit them fall back to the network defaults so the
+    // columns are never null.
+    let (cu_limit, cu_price) = decode_compute_budget(&message.instructions, &account_keys);
+    let cu_consumed = meta.compute_units_consumed.unwrap_or_default();
+    // `cu_price` is micro-lamports per CU; scale the bid back into lamports.
+    let prioritization_fee = cu_price.saturating_mul(cu_limit as u64) / 1_000_000;
+
+    let writable_accounts = writable_accounts(message, &account_keys, meta);
+
     Ok(json!({
         "txn_hash": Signature::try_from(tx.signature.as_slice()).context("invalid signature")?.to_string(),
         "signer": signer,
         "fee": fee,
+        "cu_limit": cu_limit,
+        "cu_consumed": cu_consumed,
+        "cu_price_micro_lamports": cu_price,
+        "prioritization_fee": prioritization_fee,
+        "writable_accounts": writable_accounts,
+    }))
+}
+
+/// Derive the compute-unit limit (capped at [`MAX_COMPUTE_UNIT_LIMIT`]) and the
+/// per-CU price in micro-lamports from a transaction's compiled instructions by
+/// decoding the compute-budget program's `SetComputeUnitLimit`/
+/// `SetComputeUnitPrice` instructions. Absent an explicit limit the runtime
+/// budgets [`DEFAULT_INSTRUCTION_COMPUTE_UNIT_LIMIT`] per instruction, and absent
+/// an explicit price the bid is [`DEFAULT_COMPUTE_UNIT_PRICE`].
+fn decode_compute_budget(
+    instructions: &[yellowstone_grpc_proto::prelude::CompiledInstruction],
+    account_keys: &[String],
+) -> (u32, u64) {
+    let mut cu_limit = DEFAULT_INSTRUCTION_COMPUTE_UNIT_LIMIT
+        .saturating_mul(instructions.len() as u32)
+        .min(MAX_COMPUTE_UNIT_LIMIT);
+    let mut cu_price = DEFAULT_COMPUTE_UNIT_PRICE;
+    for ix in instructions {
+        if account_keys
+            .get(ix.program_id_index as usize)
+            .map(String::as_str)
+            != Some(COMPUTE_BUDGET_PROGRAM_ID)
+        {
+            continue;
+        }
+        match ix.data.first().copied() {
+            Some(SET_COMPUTE_UNIT_LIMIT_DISCRIMINATOR) if ix.data.len() >= 5 => {
+                cu_limit = u32::from_le_bytes(ix.data[1..5].try_into().unwrap())
+                    .min(MAX_COMPUTE_UNIT_LIMIT);
+            }
+            Some(SET_COMPUTE_UNIT_PRICE_DISCRIMINATOR) if ix.data.len() >= 9 => {
+                cu_price = u64::from_le_bytes(ix.data[1..9].try_into().unwrap());
+            }
+            _ => {}
+        }
+    }
+    (cu_limit, cu_price)
+}
+
+/// Collect the pubkeys this transaction writes to: the statically writable keys
+/// (derived from the message header) plus any writable addresses resolved from
+/// address-lookup tables.
+fn writable_accounts(
+    message: &yellowstone_grpc_proto::prelude::Message,
+    account_keys: &[String],
+    meta: &yellowstone_grpc_proto::prelude::TransactionStatusMeta,
+) -> Vec<String> {
+    let mut writable = Vec::new();
+    if let Some(header) = message.header.as_ref() {
+        writable.extend(statically_writable_keys(
+            header.num_required_signatures as usize,
+            header.num_readonly_signed_accounts as usize,
+            header.num_readonly_unsigned_accounts as usize,
+            account_keys,
+        ));
+    }
+    if let Ok(loaded) = create_pubkey_vec(meta.loaded_writable_addresses.clone()) {
+        writable.extend(loaded.iter().map(|key| key.to_string()));
+    }
+    writable
+}
+
+/// Derive the statically writable account keys from a message header: the first
+/// `num_required_signatures` keys are signers (the last `num_readonly_signed` of
+/// which are read-only), and the remaining unsigned keys are writable except for
+/// the trailing `num_readonly_unsigned`.
+fn statically_writable_keys(
+    num_required_signatures: usize,
+    num_readonly_signed: usize,
+    num_readonly_unsigned: usize,
+    account_keys: &[String],
+) -> Vec<String> {
+    let mut writable = Vec::new();
+    for (index, key) in account_keys.iter().enumerate() {
+        let is_writable = if index < num_required_signatures {
+            index < num_required_signatures.saturating_sub(num_readonly_signed)
+        } else {
+            index < account_keys.len().saturating_sub(num_readonly_unsigned)
+        };
+        if is_writable {
+            writable.push(key.clone());
+        }
+    }
+    writable
+}
+
+/// Build the persisted snapshot of an account write: its pubkey, owner,
+/// lamports, slot, write version and data length.
+fn create_pretty_account(event: &AccountEvent) -> anyhow::Result<Value> {
+    let account = &event.account;
+    let pubkey = create_pubkey_vec(vec![account.pubkey.clone()])
+        .map_err(|error| anyhow::anyhow!("invalid account pubkey: {error:?}"))?
+        .first()
+        .context("missing account pubkey")?
+        .to_string();
+    let owner = create_pubkey_vec(vec![account.owner.clone()])
+        .map_err(|error| anyhow::anyhow!("invalid account owner: {error:?}"))?
+        .first()
+        .context("missing account owner")?
+        .to_string();
+    Ok(json!({
+        "pubkey": pubkey,
+        "owner": owner,
+        "lamports": account.lamports,
+        "slot": event.slot,
+        "write_version": account.write_version,
+        "data_len": account.data.len() as i64,
     }))
 }
 
-fn print_update(kind: &str, created_at: SystemTime, filters: &[String], value: Value) {
+/// Build the persisted record of a slot update: the slot number, its parent and
+/// the commitment level it transitioned into.
+fn create_pretty_slot(event: &SlotEvent) -> Value {
+    let slot = &event.slot;
+    json!({
+        "slot": slot.slot,
+        "parent": slot.parent,
+        "commitment": slot.status,
+    })
+}
+
+fn print_update(kind: &str, created_at: SystemTime, filters: &[String], value: &Value) {
     let unix_since = created_at
         .duration_since(UNIX_EPOCH)
         .expect("valid system time");
@@ -252,18 +789,189 @@ fn print_update(kind: &str, created_at: SystemTime, filters: &[String], value: V
         filters.join(","),
         unix_since.as_secs(),
         unix_since.subsec_micros(),
-        serde_json::to_string(&value).expect("json serialization failed")
+        serde_json::to_string(value).expect("json serialization failed")
     );
 }
 
-async fn add_record(pool: &PgPool, data: &Value) -> anyhow::Result<()> {
-    let sql_query = format!(
-        r#"INSERT INTO txns ( txn_hash, unix_epoch, signer, fee ) VALUES ( '{}', {}, '{}', {} )"#,
-        data["txn_hash"].as_str().unwrap(),
-        data["unix_epoch"].as_i64().unwrap(),
-        data["signer"].as_str().unwrap(),
-        data["fee"].as_i64().unwrap()
-    );
-    sqlx::query(&sql_query).execute(pool).await?;
+/// Bind a JSON integer as `i64`, reinterpreting the full `u64` range by bit
+/// pattern. Columns like `cu_price_micro_lamports`, `lamports` and
+/// `write_version` are `u64` and can exceed `i64::MAX` — `cu_price` in
+/// particular is decoded straight from adversary-controlled instruction bytes —
+/// so a naive `as_i64()` would return `None` and panic the consumer. The bits
+/// round-trip through a `BIGINT` column and back out with `as u64` downstream.
+fn json_u64_as_i64(value: &Value) -> i64 {
+    value
+        .as_i64()
+        .or_else(|| value.as_u64().map(|v| v as i64))
+        .unwrap_or_default()
+}
+
+/// Shared flush body: send `buffer` as the single multi-row, parameterized
+/// INSERT produced by `build`, then clear the buffer. A no-op when the buffer is
+/// empty. Transient Postgres errors are retried with backoff rather than
+/// panicking, so a momentary DB hiccup never discards the buffered batch; the
+/// buffer is only cleared once the insert lands.
+async fn flush_buffer(
+    pool: &PgPool,
+    buffer: &mut Vec<Value>,
+    build: impl Fn(&[Value]) -> QueryBuilder<'static, Postgres>,
+) -> anyhow::Result<()> {
+    if buffer.is_empty() {
+        return Ok(());
+    }
+    retry(ExponentialBackoff::default(), || async {
+        build(buffer.as_slice())
+            .build()
+            .execute(pool)
+            .await
+            .map_err(backoff::Error::transient)?;
+        Ok::<(), backoff::Error<sqlx::Error>>(())
+    })
+    .await?;
+    buffer.clear();
     Ok(())
 }
+
+/// Flush the buffered transaction records.
+async fn flush_records(pool: &PgPool, buffer: &mut Vec<Value>) -> anyhow::Result<()> {
+    flush_buffer(pool, buffer, |rows| {
+        let mut builder: QueryBuilder<Postgres> = QueryBuilder::new(
+            "INSERT INTO txns ( txn_hash, unix_epoch, signer, fee, cu_limit, cu_consumed, \
+             cu_price_micro_lamports, prioritization_fee, writable_accounts ) ",
+        );
+        builder.push_values(rows.iter(), |mut row, data| {
+            let writable: Vec<String> = data["writable_accounts"]
+                .as_array()
+                .map(|accounts| {
+                    accounts
+                        .iter()
+                        .filter_map(|account| account.as_str().map(str::to_string))
+                        .collect()
+                })
+                .unwrap_or_default();
+            row.push_bind(data["txn_hash"].as_str().unwrap().to_string())
+                .push_bind(json_u64_as_i64(&data["unix_epoch"]))
+                .push_bind(data["signer"].as_str().unwrap().to_string())
+                .push_bind(json_u64_as_i64(&data["fee"]))
+                .push_bind(json_u64_as_i64(&data["cu_limit"]))
+                .push_bind(json_u64_as_i64(&data["cu_consumed"]))
+                .push_bind(json_u64_as_i64(&data["cu_price_micro_lamports"]))
+                .push_bind(json_u64_as_i64(&data["prioritization_fee"]))
+                .push_bind(writable);
+        });
+        builder
+    })
+    .await
+}
+
+/// Flush the buffered account snapshots.
+async fn flush_accounts(pool: &PgPool, buffer: &mut Vec<Value>) -> anyhow::Result<()> {
+    flush_buffer(pool, buffer, |rows| {
+        let mut builder: QueryBuilder<Postgres> = QueryBuilder::new(
+            "INSERT INTO accounts ( pubkey, owner, lamports, slot, write_version, data_len ) ",
+        );
+        builder.push_values(rows.iter(), |mut row, data| {
+            row.push_bind(data["pubkey"].as_str().unwrap().to_string())
+                .push_bind(data["owner"].as_str().unwrap().to_string())
+                .push_bind(json_u64_as_i64(&data["lamports"]))
+                .push_bind(json_u64_as_i64(&data["slot"]))
+                .push_bind(json_u64_as_i64(&data["write_version"]))
+                .push_bind(json_u64_as_i64(&data["data_len"]));
+        });
+        builder
+    })
+    .await
+}
+
+/// Flush the buffered slot records.
+async fn flush_slots(pool: &PgPool, buffer: &mut Vec<Value>) -> anyhow::Result<()> {
+    flush_buffer(pool, buffer, |rows| {
+        let mut builder: QueryBuilder<Postgres> =
+            QueryBuilder::new("INSERT INTO slots ( slot, parent, commitment ) ");
+        builder.push_values(rows.iter(), |mut row, data| {
+            row.push_bind(json_u64_as_i64(&data["slot"]))
+                .push_bind(data["parent"].as_u64().map(|parent| parent as i64))
+                .push_bind(json_u64_as_i64(&data["commitment"]));
+        });
+        builder
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use yellowstone_grpc_proto::prelude::CompiledInstruction;
+
+    /// A compute-budget instruction whose program id is `account_keys[0]`.
+    fn budget_ix(data: Vec<u8>) -> CompiledInstruction {
+        CompiledInstruction {
+            program_id_index: 0,
+            accounts: vec![],
+            data,
+        }
+    }
+
+    #[test]
+    fn decode_compute_budget_reads_limit_and_price() {
+        let keys = vec![COMPUTE_BUDGET_PROGRAM_ID.to_string()];
+        let mut limit = vec![SET_COMPUTE_UNIT_LIMIT_DISCRIMINATOR];
+        limit.extend_from_slice(&50_000u32.to_le_bytes());
+        let mut price = vec![SET_COMPUTE_UNIT_PRICE_DISCRIMINATOR];
+        price.extend_from_slice(&1_234u64.to_le_bytes());
+        let (cu_limit, cu_price) =
+            decode_compute_budget(&[budget_ix(limit), budget_ix(price)], &keys);
+        assert_eq!(cu_limit, 50_000);
+        assert_eq!(cu_price, 1_234);
+    }
+
+    #[test]
+    fn decode_compute_budget_defaults_scale_with_instruction_count_and_cap() {
+        // A program that is not the compute-budget program, so no limit is set.
+        let keys = vec!["Vote111111111111111111111111111111111111111".to_string()];
+        let other = || CompiledInstruction {
+            program_id_index: 0,
+            accounts: vec![],
+            data: vec![],
+        };
+        // Two instructions → 2 × the per-instruction default, still under the cap.
+        let (cu_limit, cu_price) = decode_compute_budget(&[other(), other()], &keys);
+        assert_eq!(cu_limit, 2 * DEFAULT_INSTRUCTION_COMPUTE_UNIT_LIMIT);
+        assert_eq!(cu_price, DEFAULT_COMPUTE_UNIT_PRICE);
+        // Enough instructions to blow past the ceiling → capped.
+        let many = vec![other(); 100];
+        let (capped, _) = decode_compute_budget(&many, &keys);
+        assert_eq!(capped, MAX_COMPUTE_UNIT_LIMIT);
+    }
+
+    #[test]
+    fn statically_writable_keys_follows_header() {
+        let keys: Vec<String> = ["a", "b", "c", "d"].iter().map(|k| k.to_string()).collect();
+        // 2 signers (1 read-only) + 2 unsigned (1 read-only): writable are the
+        // first signer and the first unsigned key.
+        let writable = statically_writable_keys(2, 1, 1, &keys);
+        assert_eq!(writable, vec!["a".to_string(), "c".to_string()]);
+    }
+
+    #[test]
+    fn seen_set_drops_duplicates() {
+        let mut seen: SeenSet<u64> = SeenSet::new(4);
+        assert!(seen.insert(7), "first sighting is new");
+        assert!(!seen.insert(7), "second sighting is a duplicate");
+        assert!(seen.insert(8), "a different key is new");
+    }
+
+    #[test]
+    fn seen_set_evicts_oldest_when_full() {
+        let mut seen: SeenSet<u64> = SeenSet::new(2);
+        assert!(seen.insert(1));
+        assert!(seen.insert(2));
+        // Capacity is full; inserting 3 evicts the oldest key (1).
+        assert!(seen.insert(3));
+        // 1 was evicted, so it reads as new again...
+        assert!(seen.insert(1));
+        // ...which in turn evicts 2, while 3 is still retained.
+        assert!(!seen.insert(3));
+        assert!(seen.insert(2));
+    }
+}